@@ -0,0 +1,44 @@
+use esp_hal::{
+    adc::{Adc, AdcCalCurve, AdcConfig, AdcPin, Attenuation},
+    analog::adc::ADC1,
+    delay::DelayNs,
+    gpio::GpioPin,
+    peripheral::Peripheral,
+};
+
+// The Feather divides VBAT by 2 before it reaches the ADC pin.
+const VOLTAGE_DIVIDER_RATIO: f32 = 2.0;
+const SAMPLE_COUNT: u32 = 8;
+
+/// Samples the battery-monitor ADC pin and returns the actual battery voltage,
+/// corrected for the board's 2:1 divider. Call this while `neopixel_and_i2c_power`
+/// is high, since the battery-monitor divider shares that rail.
+pub fn read_voltage<const PIN: u8>(
+    adc1: ADC1,
+    pin: impl Peripheral<P = GpioPin<PIN>>,
+    delay: &mut impl DelayNs,
+) -> f32 {
+    let mut adc_config = AdcConfig::new();
+    // Curve calibration makes `read_oneshot` return millivolts instead of raw counts.
+    let mut adc_pin: AdcPin<_, ADC1, AdcCalCurve<ADC1>> =
+        adc_config.enable_pin_with_cal::<_, AdcCalCurve<ADC1>>(pin, Attenuation::Attenuation11dB);
+    let mut adc = Adc::new(adc1, adc_config);
+
+    let mut total_mv: u32 = 0;
+    let mut good_samples: u32 = 0;
+    for _ in 0..SAMPLE_COUNT {
+        if let Ok(sample) = nb::block!(adc.read_oneshot(&mut adc_pin)) {
+            let sample: u16 = sample;
+            total_mv += sample as u32;
+            good_samples += 1;
+        }
+        delay.delay_ms(1u32);
+    }
+
+    if good_samples == 0 {
+        return 0.0;
+    }
+
+    let average_mv = (total_mv / good_samples) as f32;
+    (average_mv / 1000.0) * VOLTAGE_DIVIDER_RATIO
+}
@@ -0,0 +1,30 @@
+use esp_hal::{
+    gpio::RtcPin,
+    rtc_cntl::sleep::{Ext1WakeupSource, WakeupLevel},
+};
+
+const NUM_MODES: u8 = 3;
+
+/// Which view `Display::draw` should render, cycled by the wake button.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DisplayMode {
+    BigNumber,
+    Stats,
+    FullHistory,
+}
+
+impl DisplayMode {
+    pub fn from_counter(counter: u8) -> Self {
+        match counter % NUM_MODES {
+            0 => DisplayMode::BigNumber,
+            1 => DisplayMode::Stats,
+            _ => DisplayMode::FullHistory,
+        }
+    }
+}
+
+/// Wakes the chip from deep sleep when the mode button is pulled low, in addition to
+/// the existing timer wakeup.
+pub fn wakeup_source<P: RtcPin>(pin: &mut P) -> Ext1WakeupSource<'_, 1> {
+    Ext1WakeupSource::new(&mut [pin], WakeupLevel::Low)
+}
@@ -0,0 +1,11 @@
+use esp_hal::rtc_cntl::Rtc;
+
+// Fixed epoch (November 2023), used as a stand-in "first boot" time since the board has no
+// battery-backed wall clock or network time sync. `Rtc::time_since_boot` keeps advancing
+// across deep sleep, so adding it to this epoch gives every reading a stable, monotonically
+// increasing timestamp good enough for relative "how old is this" labels on the graph.
+const BOOT_EPOCH_S: u64 = 1_700_000_000;
+
+pub fn now_s(rtc: &Rtc) -> u64 {
+    BOOT_EPOCH_S + rtc.time_since_boot().ticks() / 1_000_000
+}
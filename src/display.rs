@@ -1,3 +1,4 @@
+use crate::button::DisplayMode;
 use crate::history::History;
 use core::fmt::Write;
 use embedded_graphics::{
@@ -21,6 +22,16 @@ use u8g2_fonts::{
     FontRenderer,
 };
 
+// Number of most-recent samples shown in the default big-number view's graph;
+// the full-history view shows everything the ring buffer holds instead.
+const RECENT_WINDOW: usize = 24;
+
+// Horizontal guideline levels drawn on the graph to call out elevated CO2, and the
+// dash/gap pattern used to render them.
+const GUIDELINE_PPM: [u16; 2] = [1000, 1400];
+const DASH_LEN: i32 = 4;
+const GAP_LEN: i32 = 4;
+
 pub struct Display<SPI, BUSY, DC, RST, DELAY> {
     epd: Epd1in54<SPI, BUSY, DC, RST, DELAY>,
     display: Display1in54,
@@ -70,6 +81,7 @@ where
         history: &History,
         temperature: f32,
         battery_voltage: f32,
+        mode: DisplayMode,
     ) -> Result<(), SPI::Error> {
         self.epd
             .set_lut(&mut self.spi, &mut self.delay, Some(RefreshLut::Full))?;
@@ -77,10 +89,24 @@ where
         self.draw_temperature(temperature);
         self.draw_voltage(battery_voltage);
 
-        if history.len() > 0 {
-            let latest_co2 = history.data_for_display().1.last().expect("History should not be empty");
-            self.draw_co2(*latest_co2);
-            self.draw_graph(history);
+        match mode {
+            DisplayMode::BigNumber => {
+                if history.len() > 0 {
+                    let latest_co2 = history
+                        .data_for_display()
+                        .1
+                        .last()
+                        .expect("History should not be empty");
+                    self.draw_co2(*latest_co2);
+                    self.draw_graph(history, RECENT_WINDOW);
+                }
+            }
+            DisplayMode::Stats => self.draw_stats(history),
+            DisplayMode::FullHistory => {
+                if history.len() > 0 {
+                    self.draw_graph(history, history.len());
+                }
+            }
         }
 
         self.epd
@@ -157,24 +183,138 @@ where
             .unwrap();
     }
 
-    fn draw_graph(&mut self, history: &History) {
+    fn draw_stats(&mut self, history: &History) {
+        let Some((min, max, avg)) = history.stats() else {
+            return;
+        };
+
+        let stats_font = FontRenderer::new::<fonts::u8g2_font_fub20_tr>();
+        let center_x = self.display.bounding_box().center().x;
+        for (label, value, y) in [("min", min, -40), ("avg", avg, 0), ("max", max, 40)] {
+            let mut text = String::<32>::new();
+            let _ = write!(&mut text, "{label} {value}");
+            stats_font
+                .render_aligned(
+                    text.as_str(),
+                    Point::new(center_x, self.display.bounding_box().center().y + y),
+                    VerticalPosition::Baseline,
+                    HorizontalAlignment::Center,
+                    FontColor::Transparent(Color::Black),
+                    &mut self.display,
+                )
+                .unwrap();
+        }
+    }
+
+    fn draw_graph(&mut self, history: &History, window: usize) {
         // Swapped because the display is rotated.
         let width = self.epd.height() as i32;
         let height = self.epd.width() as i32;
 
-        let history_length = history.len();
+        let history_length = window.min(history.len());
+        let offset = history.len() - history_length;
 
-        // Find max value.
-        let max_co2 = history.max_value().expect("No history to display");
+        // The ceiling never drops below the top guideline level, so while readings stay
+        // under it the guideline lines keep a stable screen position between refreshes;
+        // higher readings raise the ceiling to the window max.
+        let window_max = (offset..history.len())
+            .map(|i| history.at(i))
+            .max()
+            .expect("No history to display");
+        let max_co2 = window_max.max(GUIDELINE_PPM.iter().copied().max().unwrap());
 
         for i in 0..(history_length - 1) {
             let x0 = ((i as i32) * width) / ((history_length - 1) as i32);
             let x1 = (((i + 1) as i32) * width) / ((history_length - 1) as i32);
-            let y0 = height - ((history.at(i) as i32) * height) / (max_co2 as i32);
-            let y1 = height - ((history.at(i + 1) as i32) * height) / (max_co2 as i32);
+            let y0 = height - ((history.at(offset + i) as i32) * height) / (max_co2 as i32);
+            let y1 = height - ((history.at(offset + i + 1) as i32) * height) / (max_co2 as i32);
             let _ = Line::new(Point::new(x0, y0), Point::new(x1, y1))
                 .into_styled(PrimitiveStyle::with_stroke(Color::Black, 2))
                 .draw(&mut self.display);
         };
+
+        self.draw_guidelines(max_co2, width, height);
+        self.draw_time_axis(history, offset, width, height);
+    }
+
+    fn draw_guidelines(&mut self, max_co2: u16, width: i32, height: i32) {
+        let label_font = FontRenderer::new::<fonts::u8g2_font_5x8_tr>();
+
+        for ppm in GUIDELINE_PPM {
+            if ppm > max_co2 {
+                continue;
+            }
+            let y = height - ((ppm as i32) * height) / (max_co2 as i32);
+
+            let mut x = 0;
+            while x < width {
+                let dash_end = (x + DASH_LEN).min(width);
+                let _ = Line::new(Point::new(x, y), Point::new(dash_end, y))
+                    .into_styled(PrimitiveStyle::with_stroke(Color::Black, 1))
+                    .draw(&mut self.display);
+                x += DASH_LEN + GAP_LEN;
+            }
+
+            let mut label = String::<8>::new();
+            let _ = write!(&mut label, "{ppm}");
+            let _ = label_font.render_aligned(
+                label.as_str(),
+                Point::new(width - 2, y - 2),
+                VerticalPosition::Bottom,
+                HorizontalAlignment::Right,
+                FontColor::Transparent(Color::Black),
+                &mut self.display,
+            );
+        }
+    }
+
+    fn draw_time_axis(&mut self, history: &History, offset: usize, width: i32, height: i32) {
+        let history_length = history.len() - offset;
+        if history_length < 2 {
+            return;
+        }
+
+        let newest_ts = history.timestamp_at(history.len() - 1);
+        let oldest_ts = history.timestamp_at(offset);
+        let span_s = newest_ts.saturating_sub(oldest_ts);
+        if span_s == 0 {
+            return;
+        }
+
+        let axis_font = FontRenderer::new::<fonts::u8g2_font_5x8_tr>();
+
+        // Three ticks: the oldest sample, the midpoint, and "now".
+        for fraction in [0.0, 0.5, 1.0] {
+            let x = (fraction * width as f32) as i32;
+            let mut label = String::<8>::new();
+            if fraction >= 1.0 {
+                let _ = write!(&mut label, "now");
+            } else {
+                let age_s = span_s - (span_s as f32 * fraction) as u64;
+                let age_h = age_s / 3600;
+                if age_h > 0 {
+                    let _ = write!(&mut label, "-{age_h}h");
+                } else {
+                    let _ = write!(&mut label, "-{}m", age_s / 60);
+                }
+            }
+
+            let alignment = if fraction == 0.0 {
+                HorizontalAlignment::Left
+            } else if fraction >= 1.0 {
+                HorizontalAlignment::Right
+            } else {
+                HorizontalAlignment::Center
+            };
+
+            let _ = axis_font.render_aligned(
+                label.as_str(),
+                Point::new(x, height - 2),
+                VerticalPosition::Bottom,
+                alignment,
+                FontColor::Transparent(Color::Black),
+                &mut self.display,
+            );
+        }
     }
 }
@@ -0,0 +1,104 @@
+use heapless::Vec;
+
+const CAPACITY: usize = 64;
+
+// Weight given to each new reading in the exponential moving average.
+// Higher values track the raw signal more closely; lower values smooth harder.
+const ALPHA: f32 = 0.3;
+
+#[derive(Clone, Copy)]
+struct Sample {
+    raw: u16,
+    smoothed: u16,
+    timestamp_s: u64,
+}
+
+/// A fixed-capacity ring buffer of CO2 readings, kept in `rtc_fast` RAM across deep sleeps.
+/// Each reading is stored alongside an EMA-smoothed value so the graph can plot a less noisy
+/// trend line while the raw reading stays available.
+pub struct History {
+    samples: [Sample; CAPACITY],
+    len: usize,
+    next: usize,
+    avg: f32,
+}
+
+impl History {
+    pub const fn new() -> Self {
+        History {
+            samples: [Sample {
+                raw: 0,
+                smoothed: 0,
+                timestamp_s: 0,
+            }; CAPACITY],
+            len: 0,
+            next: 0,
+            avg: 0.0,
+        }
+    }
+
+    pub fn add_measurement(&mut self, value: u16, timestamp_s: u64) {
+        self.avg = if self.len == 0 {
+            value as f32
+        } else {
+            self.avg * (1.0 - ALPHA) + (value as f32) * ALPHA
+        };
+
+        self.samples[self.next] = Sample {
+            raw: value,
+            smoothed: libm::roundf(self.avg) as u16,
+            timestamp_s,
+        };
+        self.next = (self.next + 1) % CAPACITY;
+        if self.len < CAPACITY {
+            self.len += 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    fn sample(&self, index: usize) -> Sample {
+        let start = if self.len < CAPACITY { 0 } else { self.next };
+        self.samples[(start + index) % CAPACITY]
+    }
+
+    /// The smoothed value at `index` (0 = oldest), used for the graphed trend line.
+    pub fn at(&self, index: usize) -> u16 {
+        self.sample(index).smoothed
+    }
+
+    /// The raw, unsmoothed reading at `index`, used by `stats()` to report true min/max/average.
+    fn raw_at(&self, index: usize) -> u16 {
+        self.sample(index).raw
+    }
+
+    pub fn timestamp_at(&self, index: usize) -> u64 {
+        self.sample(index).timestamp_s
+    }
+
+    pub fn data_for_display(&self) -> (usize, Vec<u16, CAPACITY>) {
+        let values = (0..self.len).map(|i| self.at(i)).collect();
+        (self.len, values)
+    }
+
+    /// Returns `(min, max, average)` of the raw readings, for the statistics display mode.
+    pub fn stats(&self) -> Option<(u16, u16, u16)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let mut min = u16::MAX;
+        let mut max = 0u16;
+        let mut sum: u32 = 0;
+        for i in 0..self.len {
+            let value = self.raw_at(i);
+            min = min.min(value);
+            max = max.max(value);
+            sum += value as u32;
+        }
+
+        Some((min, max, (sum / self.len as u32) as u16))
+    }
+}
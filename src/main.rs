@@ -1,8 +1,13 @@
 #![no_std]
 #![no_main]
 
+mod battery;
+mod button;
+mod clock;
 mod display;
 mod history;
+mod power;
+mod status_led;
 mod sunrise;
 
 use core::{
@@ -22,10 +27,11 @@ use esp_hal::{
     gpio::{Input, Io, Level, NoPin, Output, Pull},
     i2c::I2c,
     prelude::*,
+    rmt::{Rmt, TxChannelCreator},
     rtc_cntl::{
         get_reset_reason, get_wakeup_cause,
         sleep::{RtcSleepConfig, TimerWakeupSource},
-        Rtc, SocResetReason,
+        Rtc, SleepSource, SocResetReason,
     },
     spi::{master::Spi, SpiMode},
     Cpu,
@@ -38,6 +44,12 @@ static mut HISTORY: History = History::new();
 #[ram(rtc_fast)]
 static mut CALIBRATION_DATA: CalibrationData = CalibrationData::new();
 
+#[ram(rtc_fast)]
+static mut SLEEP_SCHEDULE: power::SleepSchedule = power::SleepSchedule::new();
+
+#[ram(rtc_fast)]
+static mut DISPLAY_MODE_COUNTER: u8 = 0;
+
 #[entry]
 fn main() -> ! {
     let peripherals = esp_hal::init(esp_hal::Config::default());
@@ -50,14 +62,26 @@ fn main() -> ! {
     let wake_reason = get_wakeup_cause();
     println!("wake reason: {:?}", wake_reason);
 
+    // A press of the mode button (rather than the timer) cycles the display view.
+    if wake_reason == SleepSource::Ext1 {
+        unsafe {
+            DISPLAY_MODE_COUNTER = DISPLAY_MODE_COUNTER.wrapping_add(1);
+        }
+    }
+    let display_mode = button::DisplayMode::from_counter(unsafe { DISPLAY_MODE_COUNTER });
+
     let mut neopixel_and_i2c_power = Output::new(io.pins.gpio20, Level::Low);
 
     let mut temperature = 0.0;
+    let mut battery_voltage = 0.0;
 
     if true {
         // Required for I2C to work!
         neopixel_and_i2c_power.set_high();
 
+        // The battery-monitor divider shares the same rail, so read it while power is up.
+        battery_voltage = battery::read_voltage(peripherals.ADC1, io.pins.gpio2, &mut delay);
+
         let co2_enable = Output::new(io.pins.gpio3, Level::High);
         let sda = io.pins.gpio19;
         let scl = io.pins.gpio18;
@@ -83,14 +107,26 @@ fn main() -> ! {
         rtc.sleep_light(&[&timer]);
 
         unsafe {
+            let timestamp_s = clock::now_s(&rtc);
             match co2_sensor.get_co2(&mut CALIBRATION_DATA) {
                 Ok(co2) => {
                     println!("CO2: {} ppm", co2);
-                    HISTORY.add_measurement(co2);
+                    HISTORY.add_measurement(co2, timestamp_s);
+
+                    let rmt = Rmt::new(peripherals.RMT, 80u32.MHz()).unwrap();
+                    let neopixel = io.pins.gpio9;
+                    let channel = rmt.channel0.configure(
+                        neopixel,
+                        esp_hal::rmt::TxChannelConfig {
+                            clk_divider: 1,
+                            ..Default::default()
+                        },
+                    ).unwrap();
+                    status_led::flash_ppm(channel, &mut delay, co2);
                 }
                 Err(e) => {
                     println!("Error: {:?}", e);
-                    HISTORY.add_measurement(0);
+                    HISTORY.add_measurement(0, timestamp_s);
                 }
             }
             CALIBRATION_DATA.update_time_ms(rtc.time_since_boot().ticks() / 1000);
@@ -102,8 +138,6 @@ fn main() -> ! {
         co2_sensor.turn_off();
     }
 
-    let battery_voltage = 0.0;
-
     if true {
         let sck = io.pins.gpio21;
         let mosi = io.pins.gpio22;
@@ -125,7 +159,7 @@ fn main() -> ! {
 
         unsafe {
             display
-                .draw(&HISTORY, temperature, battery_voltage)
+                .draw(&HISTORY, temperature, battery_voltage, display_mode)
                 .expect("Failed to draw to the display");
         }
     }
@@ -134,14 +168,23 @@ fn main() -> ! {
     // See https://learn.adafruit.com/adafruit-esp32-c6-feather/low-power-use
     neopixel_and_i2c_power.set_low();
 
-    // Deep sleep.
+    // Deep sleep. Sleep longer when CO2 is low and flat, shorter when it's high or rising fast.
     let mut delay = Delay::new();
-    let timer = TimerWakeupSource::new(Duration::from_secs(0));
+    let sleep_secs = unsafe {
+        SLEEP_SCHEDULE.update(&HISTORY);
+        SLEEP_SCHEDULE.interval_secs()
+    };
+    println!("next sleep: {}s", sleep_secs);
+    let timer = TimerWakeupSource::new(Duration::from_secs(sleep_secs));
     println!("sleeping!");
     delay.delay_ms(100u32);
 
+    // The mode button is wired to wake the chip directly, independent of the timer.
+    let mut mode_button = io.pins.gpio4;
+    let button_wakeup = button::wakeup_source(&mut mode_button);
+
     let cfg = RtcSleepConfig::deep();
     //cfg.set_rtc_fastmem_pd_en(false);
-    rtc.sleep(&cfg, &[&timer]);
+    rtc.sleep(&cfg, &[&timer, &button_wakeup]);
     panic!("We should never get here after the sleep() call.");
 }
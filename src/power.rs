@@ -0,0 +1,50 @@
+use crate::history::History;
+
+const MIN_SLEEP_SECS: u64 = 60;
+const MAX_SLEEP_SECS: u64 = 15 * 60;
+
+const HIGH_PPM_THRESHOLD: u16 = 1200;
+const RISING_SLOPE_THRESHOLD: f32 = 15.0; // ppm per sample
+const SLOPE_WINDOW: usize = 5;
+
+/// Chooses how long to deep-sleep before the next reading, trading battery life for
+/// responsiveness only when the air quality is actually changing. Persisted in
+/// `rtc_fast` RAM alongside `CalibrationData` so it survives deep sleep.
+pub struct SleepSchedule {
+    interval_s: u64,
+}
+
+impl SleepSchedule {
+    pub const fn new() -> Self {
+        SleepSchedule {
+            interval_s: MAX_SLEEP_SECS,
+        }
+    }
+
+    pub fn interval_secs(&self) -> u64 {
+        self.interval_s
+    }
+
+    pub fn update(&mut self, history: &History) {
+        let len = history.len();
+        if len == 0 {
+            self.interval_s = MAX_SLEEP_SECS;
+            return;
+        }
+
+        let latest = history.at(len - 1);
+        let slope = if len > SLOPE_WINDOW {
+            let k = SLOPE_WINDOW;
+            (history.at(len - 1) as f32 - history.at(len - 1 - k) as f32) / k as f32
+        } else {
+            0.0
+        };
+
+        let urgent = latest > HIGH_PPM_THRESHOLD || slope > RISING_SLOPE_THRESHOLD;
+        self.interval_s = if urgent {
+            MIN_SLEEP_SECS
+        } else {
+            MAX_SLEEP_SECS
+        };
+    }
+}
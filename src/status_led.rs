@@ -0,0 +1,38 @@
+use esp_hal::{delay::DelayNs, rmt::{Channel, TxChannel}};
+use esp_hal_smartled::{smart_led_buffer, SmartLedsAdapter};
+use smart_leds::{SmartLedsWrite, RGB8};
+
+// ppm bands for the onboard NeoPixel, tuned from general indoor air-quality guidance.
+const GREEN_MAX_PPM: u16 = 800;
+const YELLOW_MAX_PPM: u16 = 1200;
+const ORANGE_MAX_PPM: u16 = 2000;
+
+const BRIGHTNESS: u8 = 40;
+const FLASH_MS: u32 = 150;
+
+fn color_for_ppm(ppm: u16) -> RGB8 {
+    if ppm < GREEN_MAX_PPM {
+        RGB8::new(0, BRIGHTNESS, 0)
+    } else if ppm < YELLOW_MAX_PPM {
+        RGB8::new(BRIGHTNESS, BRIGHTNESS, 0)
+    } else if ppm < ORANGE_MAX_PPM {
+        RGB8::new(BRIGHTNESS, BRIGHTNESS / 2, 0)
+    } else {
+        RGB8::new(BRIGHTNESS, 0, 0)
+    }
+}
+
+/// Flashes the onboard NeoPixel with a color representing `ppm`, then turns it back off.
+/// `neopixel_and_i2c_power` must already be high before calling this.
+pub fn flash_ppm<Tx: TxChannel>(
+    channel: Channel<esp_hal::Blocking, Tx>,
+    delay: &mut impl DelayNs,
+    ppm: u16,
+) {
+    let mut buffer = smart_led_buffer!(1);
+    let mut led = SmartLedsAdapter::new(channel, &mut buffer);
+
+    let _ = led.write([color_for_ppm(ppm)].into_iter());
+    delay.delay_ms(FLASH_MS);
+    let _ = led.write([RGB8::default()].into_iter());
+}